@@ -6,79 +6,572 @@ use bdk::chain::{
     keychain::{KeychainChangeSet, KeychainTracker, PersistBackend},
     sparse_chain::ChainPosition,
 };
-use sled::IVec;
+
+/// Converts between a value and its on-disk byte representation.
+///
+/// [`SledStore`] is generic over this trait so callers can swap the default [`BincodeSerDe`] for
+/// another encoding (e.g. [`JsonSerDe`]) without touching the rest of the store, mirroring the
+/// `SerDe`/`BincodeSerDe` split in `typed-sled`.
+pub trait SerDe<T> {
+    /// Error returned when `bytes` cannot be decoded back into `T`.
+    type DeError: std::error::Error + Send + Sync + 'static;
+
+    /// Encodes `value` into its on-disk byte representation.
+    ///
+    /// Unlike [`SerDe::deserialize`], which must surface bit-rot in already-written data as a
+    /// recoverable error, a value that fails to serialize indicates `T` itself can't round-trip
+    /// through this `SerDe` at all — a programmer error, not a storage one — so implementations
+    /// are expected to panic rather than return a `Result`.
+    fn serialize(value: &T) -> Vec<u8>;
+
+    /// Decodes `bytes` back into a `T`, or fails if `bytes` is not valid encoded `T`.
+    fn deserialize(bytes: &[u8]) -> Result<T, Self::DeError>;
+}
+
+/// The default [`SerDe`], backed by [`bincode`].
+pub struct BincodeSerDe;
+
+impl<T> SerDe<T> for BincodeSerDe
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    type DeError = bincode::Error;
+
+    fn serialize(value: &T) -> Vec<u8> {
+        bincode::serialize(value).expect("Failed to serialize value")
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<T, Self::DeError> {
+        bincode::deserialize(bytes)
+    }
+}
+
+/// A human-readable [`SerDe`], backed by [`serde_json`].
+///
+/// Useful when you want to be able to inspect or hand-edit the contents of the `sled::Tree`.
+pub struct JsonSerDe;
+
+impl<T> SerDe<T> for JsonSerDe
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    type DeError = serde_json::Error;
+
+    fn serialize(value: &T) -> Vec<u8> {
+        serde_json::to_vec(value).expect("Failed to serialize value")
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<T, Self::DeError> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// Error returned by [`SledStore`] when a read or write fails, because of a `sled` I/O problem,
+/// because the on-disk data is corrupt, or because of the tree's descriptor binding.
+#[derive(Debug)]
+pub enum SledStoreError {
+    /// The underlying `sled::Tree` returned an I/O error.
+    Sled(sled::Error),
+    /// A stored changeset could not be decoded with the configured [`SerDe`].
+    Decode {
+        /// The counter index of the entry that failed to decode.
+        index: u64,
+        /// The underlying decode error.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// The counter entry exists but isn't a valid 8-byte little-endian `u64`.
+    CorruptCounter {
+        /// The length in bytes of the stored value (a valid counter is always 8 bytes).
+        len: usize,
+    },
+    /// [`SledStore::bind_descriptor`] was called with a descriptor that doesn't match the one
+    /// already bound to this tree.
+    DescriptorMismatch {
+        /// The descriptor this tree was first bound to.
+        expected: String,
+        /// The descriptor that was passed in.
+        actual: String,
+    },
+    /// This tree has a descriptor bound to it from a previous [`SledStore::bind_descriptor`]
+    /// call, but this `SledStore` instance hasn't called `bind_descriptor` yet.
+    /// `append_changeset` and `load_into_keychain_tracker` fail with this until it does, so a
+    /// fresh instance can never silently merge a different wallet's history in just by skipping
+    /// the call. [`SledStore::compact`], [`SledStore::migrate_into`] and `export_changesets` do
+    /// not check the binding.
+    DescriptorNotBound {
+        /// The descriptor already bound to this tree.
+        persisted: String,
+    },
+}
+
+impl std::fmt::Display for SledStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SledStoreError::Sled(e) => write!(f, "sled error: {}", e),
+            SledStoreError::Decode { index, source } => {
+                write!(
+                    f,
+                    "failed to decode changeset at index {}: {}",
+                    index, source
+                )
+            }
+            SledStoreError::CorruptCounter { len } => {
+                write!(f, "corrupt counter: expected 8 bytes, got {}", len)
+            }
+            SledStoreError::DescriptorMismatch { expected, actual } => write!(
+                f,
+                "descriptor mismatch: tree was bound to {:?}, got {:?}",
+                expected, actual
+            ),
+            SledStoreError::DescriptorNotBound { persisted } => write!(
+                f,
+                "tree is bound to descriptor {:?}, but bind_descriptor hasn't been called on this \
+                 SledStore instance yet",
+                persisted
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SledStoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SledStoreError::Sled(e) => Some(e),
+            SledStoreError::Decode { source, .. } => Some(source.as_ref()),
+            SledStoreError::CorruptCounter { .. } => None,
+            SledStoreError::DescriptorMismatch { .. } => None,
+            SledStoreError::DescriptorNotBound { .. } => None,
+        }
+    }
+}
+
+impl From<sled::Error> for SledStoreError {
+    fn from(error: sled::Error) -> Self {
+        SledStoreError::Sled(error)
+    }
+}
+
+/// Decodes a stored counter value, which is always an 8-byte little-endian `u64`.
+fn decode_counter(bytes: &sled::IVec) -> Result<u64, SledStoreError> {
+    let len = bytes.len();
+    let array: [u8; 8] = bytes
+        .as_ref()
+        .try_into()
+        .map_err(|_| SledStoreError::CorruptCounter { len })?;
+    Ok(u64::from_le_bytes(array))
+}
+
+/// The metadata key the counter is stored under.
+///
+/// This is never 8 bytes long, so it can never collide with a big-endian-`u64`-encoded data key.
+const COUNTER_KEY: &[u8] = b"meta:counter";
+
+/// The key the counter was stored under before the big-endian key migration.
+const LEGACY_COUNTER_KEY: &[u8] = b"counter";
+
+/// The metadata key the bound descriptor (see [`SledStore::bind_descriptor`]) is stored under.
+const DESCRIPTOR_KEY: &[u8] = b"meta:descriptor";
+
+/// Common prefix of every metadata key, so new metadata can't accidentally be mistaken for a
+/// changeset entry.
+const METADATA_KEY_PREFIX: &[u8] = b"meta:";
+
+/// Whether `key` is one of [`SledStore`]'s own metadata keys rather than a data (changeset) entry.
+fn is_metadata_key(key: &[u8]) -> bool {
+    key.starts_with(METADATA_KEY_PREFIX) || key == LEGACY_COUNTER_KEY
+}
 
 /// Implements [`PersistBackend`] for [`sled::Tree`].
 ///
 /// [`PersistBackend`]: bdk::chain::keychain::PersistBackend
-pub struct SledStore<K, P> {
+pub struct SledStore<K, P, S = BincodeSerDe> {
     db: sled::Tree,
     counter: u64,
-    phantom: std::marker::PhantomData<(K, P)>,
+    compact_threshold: Option<u64>,
+    descriptor: Option<String>,
+    phantom: std::marker::PhantomData<(K, P, S)>,
 }
 
-impl<K, P> SledStore<K, P> {
+impl<K, P, S> SledStore<K, P, S> {
     /// Creates a new `SledStore` from a `sled::Tree`.
     ///
     /// Returns an error if `db` is corrupted. You must only use either empty
     /// `sled::Tree` or one previously used by [`SledStore`].
-    pub fn new(db: sled::Tree) -> Result<Self, sled::Error> {
-        let counter_bytes = db
-            .get("counter")?
-            .unwrap_or_else(|| IVec::from(0u64.to_le_bytes().to_vec()))
-            .to_vec()
-            .as_slice()
-            .try_into()
-            .expect("Invalid counter");
+    ///
+    /// If `db` was last used before data keys were switched to big-endian encoding, it is
+    /// migrated in place before this returns.
+    pub fn new(db: sled::Tree) -> Result<Self, SledStoreError> {
+        let counter = if let Some(bytes) = db.get(COUNTER_KEY)? {
+            decode_counter(&bytes)?
+        } else if let Some(bytes) = db.get(LEGACY_COUNTER_KEY)? {
+            let counter = decode_counter(&bytes)?;
+            Self::migrate_le_keys(&db, counter)?;
+            counter
+        } else {
+            0
+        };
 
         Ok(Self {
             db,
-            counter: u64::from_le_bytes(counter_bytes),
+            counter,
+            compact_threshold: None,
+            descriptor: None,
             phantom: std::marker::PhantomData,
         })
     }
 
-    fn iter_changesets(&self) -> impl Iterator<Item = Result<KeychainChangeSet<K, P>, sled::Error>>
+    /// Binds this store to `descriptor`, enforcing that `db` is only ever used by a single
+    /// wallet.
+    ///
+    /// The first descriptor ever bound to a given `sled::Tree` is persisted under a dedicated
+    /// metadata key. Every later call — including from a fresh `SledStore` instance opening the
+    /// same tree — must supply that same descriptor, or this returns
+    /// [`SledStoreError::DescriptorMismatch`] instead of silently merging a different wallet's
+    /// changesets in. This makes the "you must only use an empty tree or one previously used by
+    /// `SledStore`" caveat on [`SledStore::new`] enforceable in code.
+    ///
+    /// Once a tree has a bound descriptor, every `append_changeset`/`load_into_keychain_tracker`
+    /// call on *any* `SledStore` instance over that tree fails with
+    /// [`SledStoreError::DescriptorNotBound`] until `bind_descriptor` is called on it — the guard
+    /// is fail-closed, not opt-in: a caller can't bypass it by simply never calling this method.
+    pub fn bind_descriptor(&mut self, descriptor: impl Into<String>) -> Result<(), SledStoreError> {
+        let descriptor = descriptor.into();
+        self.check_descriptor(&descriptor)?;
+        self.descriptor = Some(descriptor);
+        Ok(())
+    }
+
+    /// Verifies `descriptor` against the one persisted in `db`, persisting it first if none is
+    /// stored yet.
+    fn check_descriptor(&self, descriptor: &str) -> Result<(), SledStoreError> {
+        match self.db.get(DESCRIPTOR_KEY)? {
+            None => {
+                self.db.insert(DESCRIPTOR_KEY, descriptor.as_bytes())?;
+                self.db.flush()?;
+                Ok(())
+            }
+            Some(stored) => {
+                let stored = String::from_utf8_lossy(&stored).into_owned();
+                if stored == descriptor {
+                    Ok(())
+                } else {
+                    Err(SledStoreError::DescriptorMismatch {
+                        expected: stored,
+                        actual: descriptor.to_string(),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Fails closed if `db` has a descriptor bound to it that this instance hasn't confirmed via
+    /// [`SledStore::bind_descriptor`] yet, so a fresh `SledStore` over an already-bound tree can
+    /// never read or write changesets without first proving it's the same wallet.
+    fn ensure_descriptor_bound(&self) -> Result<(), SledStoreError> {
+        if let Some(descriptor) = &self.descriptor {
+            return self.check_descriptor(descriptor);
+        }
+        match self.db.get(DESCRIPTOR_KEY)? {
+            None => Ok(()),
+            Some(stored) => Err(SledStoreError::DescriptorNotBound {
+                persisted: String::from_utf8_lossy(&stored).into_owned(),
+            }),
+        }
+    }
+
+    /// Rewrites every data key in `db` from `u64::to_le_bytes` to `u64::to_be_bytes` so that
+    /// lexicographic order (what `sled::Tree::iter` yields) matches insertion order, and moves
+    /// the counter from the old [`LEGACY_COUNTER_KEY`] to [`COUNTER_KEY`].
+    fn migrate_le_keys(db: &sled::Tree, counter: u64) -> Result<(), SledStoreError> {
+        let legacy_entries: Vec<(sled::IVec, sled::IVec)> = db
+            .iter()
+            .filter_map(|k_v| match k_v {
+                Err(e) => Some(Err(e)),
+                Ok((k, _)) if k.as_ref() == LEGACY_COUNTER_KEY => None,
+                Ok(k_v) => Some(Ok(k_v)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        db.transaction(|tx_tree| {
+            tx_tree.remove(LEGACY_COUNTER_KEY)?;
+            for (key, value) in &legacy_entries {
+                let le_index = u64::from_le_bytes(key.as_ref().try_into().unwrap_or_default());
+                tx_tree.remove(key.as_ref())?;
+                tx_tree.insert(le_index.to_be_bytes().as_slice(), value.as_ref())?;
+            }
+            tx_tree.insert(COUNTER_KEY, &counter.to_le_bytes())?;
+            Ok(())
+        })
+        .map_err(
+            |e: sled::transaction::TransactionError<std::convert::Infallible>| match e {
+                sled::transaction::TransactionError::Storage(e) => e,
+                sled::transaction::TransactionError::Abort(never) => match never {},
+            },
+        )?;
+
+        db.flush()?;
+        Ok(())
+    }
+
+    /// Sets the number of stored changesets above which `append_changeset` automatically calls
+    /// [`SledStore::compact`].
+    pub fn with_compact_threshold(mut self, threshold: u64) -> Self {
+        self.compact_threshold = Some(threshold);
+        self
+    }
+
+    /// Merges every stored changeset into a single aggregated one, atomically replacing the
+    /// tree's contents with a single entry at index `0` and the counter reset to `1`.
+    ///
+    /// A long-lived wallet accumulates one entry per `append_changeset` call, all of which
+    /// `load_into_keychain_tracker` must deserialize and merge on every startup. Call this
+    /// periodically (or set [`SledStore::with_compact_threshold`]) to keep that bounded.
+    ///
+    /// Returns the number of entries removed by the compaction (the number of stored changesets
+    /// minus one); if at most one changeset is stored, nothing is rewritten and this returns `0`.
+    pub fn compact(&mut self) -> Result<usize, SledStoreError>
+    where
+        K: Ord + Clone,
+        KeychainChangeSet<K, P>: serde::Serialize + serde::de::DeserializeOwned,
+        S: SerDe<KeychainChangeSet<K, P>>,
+    {
+        // Only wipe data entries — metadata keys (the descriptor binding in particular) must
+        // survive a compaction untouched.
+        let keys: Vec<sled::IVec> = self
+            .db
+            .iter()
+            .keys()
+            .filter_map(|k| match k {
+                Ok(k) if !is_metadata_key(&k) => Some(Ok(k)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut merged: Option<KeychainChangeSet<K, P>> = None;
+        let mut count: usize = 0;
+        for changeset in self.iter_changesets() {
+            let changeset = changeset?;
+            count += 1;
+            match &mut merged {
+                Some(acc) => acc.append(changeset),
+                None => merged = Some(changeset),
+            }
+        }
+
+        let Some(merged) = merged else {
+            return Ok(0);
+        };
+        if count <= 1 {
+            return Ok(0);
+        }
+
+        let merged_bytes = S::serialize(&merged);
+
+        self.db
+            .transaction(|tx_tree| {
+                for key in &keys {
+                    tx_tree.remove(key.as_ref())?;
+                }
+                tx_tree.insert(0u64.to_be_bytes().as_slice(), merged_bytes.clone())?;
+                tx_tree.insert(COUNTER_KEY, &1u64.to_le_bytes())?;
+                Ok(())
+            })
+            .map_err(
+                |e: sled::transaction::TransactionError<std::convert::Infallible>| match e {
+                    sled::transaction::TransactionError::Storage(e) => e,
+                    sled::transaction::TransactionError::Abort(never) => match never {},
+                },
+            )?;
+
+        self.db.flush()?;
+        self.counter = 1;
+
+        Ok(count - 1)
+    }
+
+    fn iter_changesets(
+        &self,
+    ) -> impl Iterator<Item = Result<KeychainChangeSet<K, P>, SledStoreError>>
     where
         KeychainChangeSet<K, P>: serde::de::DeserializeOwned,
+        S: SerDe<KeychainChangeSet<K, P>>,
     {
-        self.db.iter().filter_map(|k_v| {
-            let Ok((k, v)) = k_v else {
-                return None;
-            };
-            if k != "counter".as_bytes() {
-                let changeset = bincode::deserialize(&v).expect("Failed to deserialize changeset");
-                Some(Ok(changeset))
-            } else {
-                None
+        self.db.iter().filter_map(|k_v| match k_v {
+            Err(e) => Some(Err(e.into())),
+            Ok((k, v)) => {
+                if is_metadata_key(k.as_ref()) {
+                    return None;
+                }
+                let index = k
+                    .as_ref()
+                    .try_into()
+                    .map(u64::from_be_bytes)
+                    .unwrap_or(u64::MAX);
+                Some(S::deserialize(&v).map_err(|source| SledStoreError::Decode {
+                    index,
+                    source: Box::new(source),
+                }))
             }
         })
     }
+
+    /// Replays every changeset held by this store into `dst`, in insertion order.
+    ///
+    /// Lets a wallet move its history from sled to any other [`PersistBackend`] (a file-backed
+    /// or SQL-backed one, for example) without losing it.
+    pub fn migrate_into<B>(
+        &self,
+        dst: &mut B,
+    ) -> Result<(), MigrateError<SledStoreError, B::WriteError>>
+    where
+        K: Ord + Clone + std::fmt::Debug,
+        P: ChainPosition,
+        KeychainChangeSet<K, P>: serde::Serialize + serde::de::DeserializeOwned,
+        S: SerDe<KeychainChangeSet<K, P>>,
+        B: PersistBackend<K, P>,
+    {
+        for changeset in self.iter_changesets() {
+            let changeset = changeset.map_err(MigrateError::Read)?;
+            dst.append_changeset(&changeset)
+                .map_err(MigrateError::Write)?;
+        }
+        Ok(())
+    }
+
+    /// Populates this store by replaying every changeset exported by `src`, the inverse of
+    /// [`SledStore::migrate_into`].
+    pub fn import_from<B>(
+        &mut self,
+        src: &B,
+    ) -> Result<(), MigrateError<B::ExportError, SledStoreError>>
+    where
+        K: Ord + Clone + std::fmt::Debug,
+        P: ChainPosition,
+        KeychainChangeSet<K, P>: serde::Serialize + serde::de::DeserializeOwned,
+        S: SerDe<KeychainChangeSet<K, P>>,
+        B: ExportBackend<K, P>,
+    {
+        for changeset in src.export_changesets() {
+            let changeset = changeset.map_err(MigrateError::Read)?;
+            self.append_changeset(&changeset)
+                .map_err(MigrateError::Write)?;
+        }
+        Ok(())
+    }
 }
 
-impl<K, P> PersistBackend<K, P> for SledStore<K, P>
+/// A [`PersistBackend`] that can also replay out every changeset it holds, in insertion order.
+///
+/// [`SledStore`] implements this so it can be used as the source of [`SledStore::import_from`];
+/// other backends can implement it too to participate in migrations either way.
+pub trait ExportBackend<K: Ord, P>: PersistBackend<K, P> {
+    /// Error returned when a stored changeset can't be read back.
+    type ExportError: std::error::Error + Send + Sync + 'static;
+
+    /// Returns every changeset this backend holds, in insertion order.
+    fn export_changesets(
+        &self,
+    ) -> impl Iterator<Item = Result<KeychainChangeSet<K, P>, Self::ExportError>>;
+}
+
+impl<K, P, S> ExportBackend<K, P> for SledStore<K, P, S>
 where
     K: Ord + Clone + std::fmt::Debug,
     P: ChainPosition,
     KeychainChangeSet<K, P>: serde::Serialize + serde::de::DeserializeOwned,
+    S: SerDe<KeychainChangeSet<K, P>>,
 {
-    type WriteError = sled::Error;
-    type LoadError = sled::Error;
+    type ExportError = SledStoreError;
+
+    fn export_changesets(
+        &self,
+    ) -> impl Iterator<Item = Result<KeychainChangeSet<K, P>, SledStoreError>> {
+        self.iter_changesets()
+    }
+}
+
+/// Error returned by [`SledStore::migrate_into`] and [`SledStore::import_from`].
+#[derive(Debug)]
+pub enum MigrateError<R, W> {
+    /// Reading a changeset out of the source backend failed.
+    Read(R),
+    /// Writing a changeset into the destination backend failed.
+    Write(W),
+}
+
+impl<R: std::fmt::Display, W: std::fmt::Display> std::fmt::Display for MigrateError<R, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrateError::Read(e) => write!(f, "failed to read changeset from source: {}", e),
+            MigrateError::Write(e) => write!(f, "failed to write changeset to destination: {}", e),
+        }
+    }
+}
+
+impl<R, W> std::error::Error for MigrateError<R, W>
+where
+    R: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrateError::Read(e) => Some(e),
+            MigrateError::Write(e) => Some(e),
+        }
+    }
+}
+
+impl<K, P, S> PersistBackend<K, P> for SledStore<K, P, S>
+where
+    K: Ord + Clone + std::fmt::Debug,
+    P: ChainPosition,
+    KeychainChangeSet<K, P>: serde::Serialize + serde::de::DeserializeOwned,
+    S: SerDe<KeychainChangeSet<K, P>>,
+{
+    type WriteError = SledStoreError;
+    type LoadError = SledStoreError;
 
     fn append_changeset(
         &mut self,
         changeset: &KeychainChangeSet<K, P>,
     ) -> Result<(), Self::WriteError> {
+        self.ensure_descriptor_bound()?;
+
         if changeset.is_empty() {
             return Ok(());
         }
 
-        self.db.insert(
-            self.counter.to_le_bytes(),
-            bincode::serialize(changeset).expect("Failed to serialize changeset"),
-        )?;
-        self.counter += 1;
-        self.db.insert("counter", &self.counter.to_le_bytes())?;
+        let next_counter = self.counter + 1;
+        let changeset_bytes = S::serialize(changeset);
+
+        // Write the changeset and the bumped counter inside a single transaction so a crash
+        // between the two writes can never leave a stale counter still pointing at the entry
+        // that was just written (which would otherwise get silently overwritten on the next
+        // append).
+        self.db
+            .transaction(|tx_tree| {
+                tx_tree.insert(
+                    self.counter.to_be_bytes().as_slice(),
+                    changeset_bytes.clone(),
+                )?;
+                tx_tree.insert(COUNTER_KEY, &next_counter.to_le_bytes())?;
+                Ok(())
+            })
+            .map_err(
+                |e: sled::transaction::TransactionError<std::convert::Infallible>| match e {
+                    sled::transaction::TransactionError::Storage(e) => e,
+                    sled::transaction::TransactionError::Abort(never) => match never {},
+                },
+            )?;
+
+        // Make sure the transaction above is durable on disk before we report success.
+        self.db.flush()?;
+        self.counter = next_counter;
+
+        if matches!(self.compact_threshold, Some(threshold) if self.counter > threshold) {
+            self.compact()?;
+        }
 
         Ok(())
     }
@@ -87,6 +580,8 @@ where
         &mut self,
         tracker: &mut KeychainTracker<K, P>,
     ) -> Result<(), Self::LoadError> {
+        self.ensure_descriptor_bound()?;
+
         for changeset in self.iter_changesets() {
             tracker.apply_changeset(changeset?)
         }
@@ -134,7 +629,7 @@ mod tests {
     fn works() {
         let tree = new_tree();
 
-        let mut store = SledStore::new(tree).unwrap();
+        let mut store: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
         assert_eq!(store.counter, 0);
 
         for (i, changeset) in test_changesets().into_iter().enumerate() {
@@ -142,7 +637,7 @@ mod tests {
 
             assert_eq!(store.counter, i as u64 + 1);
             assert_eq!(
-                store.db.get("counter").unwrap().unwrap().to_vec(),
+                store.db.get(COUNTER_KEY).unwrap().unwrap().to_vec(),
                 store.counter.to_le_bytes().to_vec()
             );
         }
@@ -151,8 +646,13 @@ mod tests {
         // `KeychainChangeSet`.
         assert_eq!(
             bincode::serialize(&test_changesets()).unwrap(),
-            bincode::serialize(&store.iter_changesets().collect::<Result<Vec<_>, _>>().unwrap())
-                .unwrap()
+            bincode::serialize(
+                &store
+                    .iter_changesets()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            )
+            .unwrap()
         );
 
         // TODO: test `load_into_keychain_tracker`.
@@ -161,9 +661,243 @@ mod tests {
     #[test]
     fn restores_counter() {
         let tree = new_tree();
-        tree.insert("counter", &42u64.to_le_bytes()).unwrap();
+        tree.insert(COUNTER_KEY, &42u64.to_le_bytes()).unwrap();
 
         let store: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
         assert_eq!(store.counter, 42);
     }
+
+    #[test]
+    fn migrates_legacy_little_endian_keys() {
+        let tree = new_tree();
+
+        // Simulate a tree written before the big-endian key migration: little-endian keys and
+        // the old `"counter"` metadata key.
+        for (i, changeset) in test_changesets().into_iter().enumerate() {
+            tree.insert(
+                (i as u64).to_le_bytes(),
+                bincode::serialize(&changeset).unwrap(),
+            )
+            .unwrap();
+        }
+        tree.insert(
+            LEGACY_COUNTER_KEY,
+            &(test_changesets().len() as u64).to_le_bytes(),
+        )
+        .unwrap();
+
+        let store: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
+        assert_eq!(store.counter, test_changesets().len() as u64);
+        assert!(store.db.get(LEGACY_COUNTER_KEY).unwrap().is_none());
+
+        // Changesets should now replay in insertion order off of big-endian keys.
+        assert_eq!(
+            bincode::serialize(&test_changesets()).unwrap(),
+            bincode::serialize(
+                &store
+                    .iter_changesets()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn works_with_json_serde() {
+        let tree = new_tree();
+
+        let mut store: SledStore<TestKeychain, TxHeight, JsonSerDe> = SledStore::new(tree).unwrap();
+
+        for changeset in test_changesets() {
+            store.append_changeset(&changeset).expect("Should apply");
+        }
+
+        assert_eq!(
+            serde_json::to_vec(&test_changesets()).unwrap(),
+            serde_json::to_vec(
+                &store
+                    .iter_changesets()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn compacts_into_a_single_changeset() {
+        let tree = new_tree();
+        let mut store: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
+
+        for changeset in test_changesets() {
+            store.append_changeset(&changeset).expect("Should apply");
+        }
+
+        let collapsed = store.compact().expect("Should compact");
+        assert_eq!(collapsed, test_changesets().len() - 1);
+        assert_eq!(store.counter, 1);
+        assert_eq!(store.iter_changesets().count(), 1);
+
+        let mut expected = test_changesets().remove(0);
+        expected.append(test_changesets().remove(1));
+        let merged = store
+            .iter_changesets()
+            .next()
+            .unwrap()
+            .expect("Should decode");
+        assert_eq!(
+            bincode::serialize(&expected).unwrap(),
+            bincode::serialize(&merged).unwrap()
+        );
+
+        // Compacting again is a no-op since there's only one entry left.
+        assert_eq!(store.compact().expect("Should compact"), 0);
+    }
+
+    #[test]
+    fn compacts_a_descriptor_bound_store() {
+        let tree = new_tree();
+        let mut store: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
+        store
+            .bind_descriptor("wpkh(first)")
+            .expect("First bind should persist");
+
+        for changeset in test_changesets() {
+            store.append_changeset(&changeset).expect("Should apply");
+        }
+
+        store
+            .compact()
+            .expect("Should compact a descriptor-bound store");
+        assert_eq!(store.iter_changesets().count(), 1);
+
+        // The descriptor binding survives the compaction untouched.
+        store
+            .bind_descriptor("wpkh(first)")
+            .expect("Descriptor binding should survive compaction");
+        let err = store
+            .bind_descriptor("wpkh(other)")
+            .expect_err("Different descriptor should still be rejected after compaction");
+        assert!(matches!(err, SledStoreError::DescriptorMismatch { .. }));
+    }
+
+    #[test]
+    fn binds_descriptor_and_rejects_mismatch() {
+        let tree = new_tree();
+
+        let mut store: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
+        store
+            .bind_descriptor("wpkh(first)")
+            .expect("First bind should persist");
+
+        // Binding the same descriptor again (e.g. a fresh instance over the same tree) succeeds.
+        store
+            .bind_descriptor("wpkh(first)")
+            .expect("Same descriptor should match");
+
+        let err = store
+            .bind_descriptor("wpkh(other)")
+            .expect_err("Different descriptor should be rejected");
+        assert!(matches!(err, SledStoreError::DescriptorMismatch { .. }));
+
+        // The store remains bound to the original descriptor, so normal operations still work
+        // and a subsequent mismatched descriptor is still rejected on every operation.
+        store
+            .append_changeset(&test_changesets()[0])
+            .expect("Append with bound descriptor should succeed");
+
+        // The bound descriptor's metadata entry must not be mistaken for a changeset.
+        assert_eq!(
+            bincode::serialize(&vec![test_changesets()[0].clone()]).unwrap(),
+            bincode::serialize(
+                &store
+                    .iter_changesets()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        let mut tracker = KeychainTracker::default();
+        store
+            .load_into_keychain_tracker(&mut tracker)
+            .expect("Load with bound descriptor should succeed");
+
+        store.descriptor = Some("wpkh(other)".to_string());
+        let err = store
+            .append_changeset(&test_changesets()[1])
+            .expect_err("Append with mismatched descriptor should be rejected");
+        assert!(matches!(err, SledStoreError::DescriptorMismatch { .. }));
+    }
+
+    #[test]
+    fn rejects_unbound_access_to_a_descriptor_bound_tree() {
+        let tree = new_tree();
+
+        let mut bound: SledStore<TestKeychain, TxHeight> = SledStore::new(tree.clone()).unwrap();
+        bound
+            .bind_descriptor("wpkh(first)")
+            .expect("First bind should persist");
+        bound
+            .append_changeset(&test_changesets()[0])
+            .expect("Append with bound descriptor should succeed");
+
+        // A fresh instance over the same (already-bound) tree, which never calls
+        // `bind_descriptor`, must not be able to read or write changesets — the guard has to be
+        // fail-closed, not opt-in.
+        let mut unbound: SledStore<TestKeychain, TxHeight> = SledStore::new(tree).unwrap();
+        let err = unbound
+            .append_changeset(&test_changesets()[1])
+            .expect_err("Append without binding should be rejected");
+        assert!(matches!(err, SledStoreError::DescriptorNotBound { .. }));
+
+        let mut tracker = KeychainTracker::default();
+        let err = unbound
+            .load_into_keychain_tracker(&mut tracker)
+            .expect_err("Load without binding should be rejected");
+        assert!(matches!(err, SledStoreError::DescriptorNotBound { .. }));
+
+        // Binding with the matching descriptor unblocks both.
+        unbound
+            .bind_descriptor("wpkh(first)")
+            .expect("Binding with the persisted descriptor should succeed");
+        unbound
+            .append_changeset(&test_changesets()[1])
+            .expect("Append should succeed once bound");
+    }
+
+    #[test]
+    fn migrates_between_stores() {
+        let mut src: SledStore<TestKeychain, TxHeight> = SledStore::new(new_tree()).unwrap();
+        for changeset in test_changesets() {
+            src.append_changeset(&changeset).expect("Should apply");
+        }
+
+        let mut dst: SledStore<TestKeychain, TxHeight> = SledStore::new(new_tree()).unwrap();
+        src.migrate_into(&mut dst).expect("Should migrate");
+
+        assert_eq!(
+            bincode::serialize(&test_changesets()).unwrap(),
+            bincode::serialize(
+                &dst.export_changesets()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+
+        let mut imported: SledStore<TestKeychain, TxHeight> = SledStore::new(new_tree()).unwrap();
+        imported.import_from(&dst).expect("Should import");
+
+        assert_eq!(
+            bincode::serialize(&test_changesets()).unwrap(),
+            bincode::serialize(
+                &imported
+                    .export_changesets()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+    }
 }